@@ -1,10 +1,17 @@
+#![no_std]
 #![warn(missing_docs, missing_debug_implementations, rust_2018_idioms)]
 
 //! Provides a data structure for tracking random-access writes to a buffer.
+//!
+//! This crate is `no_std`, relying only on `alloc` for its `Vec` and
+//! `BytesMut`/`Bytes` storage, so it can be used in embedded network
+//! stacks and firmware that reassemble fragmented transfers.
 
-use std::mem;
+extern crate alloc;
 
-use bytes::{BufMut, BytesMut};
+use alloc::vec::Vec;
+
+use bytes::{Buf, Bytes, BytesMut};
 
 use thiserror::Error;
 
@@ -18,27 +25,33 @@ pub enum Error {
     /// Attempted to write more data than would fit into the missing segment.
     #[error("Would overwrite previously received data")]
     WouldOverwrite,
+
+    /// Attempted a write that would push the number of missing
+    /// segments above the configured maximum.
+    #[error("Too many missing segments are already being tracked")]
+    TooManyHoles,
 }
 
 /// A byte buffer that tracks the locations of random-access writes.
+///
+/// Internally, the buffer is stored as a single backing `BytesMut`
+/// alongside a list of [`Contig`] entries, each describing a run of
+/// absent bytes immediately followed by a run of present bytes. This
+/// keeps storage to one allocation instead of one per tracked segment.
 #[derive(Debug)]
 pub struct BytesQuilt {
-    tail_offset: usize,
-    segments: Vec<Segment>,
-    buffer_tail: BytesMut,
-}
-
-#[derive(Copy, Clone, Debug, PartialEq)]
-enum Status {
-    Missing,
-    Received,
+    base_offset: usize,
+    buffer: BytesMut,
+    contigs: Vec<Contig>,
+    max_holes: Option<usize>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
-struct Segment {
-    status: Status,
-    offset: usize,
-    buffer: BytesMut,
+/// A run of missing bytes immediately followed by a run of bytes that
+/// have been received.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Contig {
+    hole_size: usize,
+    data_size: usize,
 }
 
 /// A description of a segment in the buffer that hasn't been written to.
@@ -58,184 +71,317 @@ impl BytesQuilt {
     /// Creates a new `BytesQuilt` with default capacity.
     pub fn new() -> Self {
         Self {
-            tail_offset: 0,
-            segments: Vec::new(),
-            buffer_tail: BytesMut::new(),
+            base_offset: 0,
+            buffer: BytesMut::new(),
+            contigs: Vec::new(),
+            max_holes: None,
         }
     }
 
     /// Creates a new `BytesQuilt` with the specified capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            tail_offset: 0,
-            segments: Vec::new(),
-            buffer_tail: BytesMut::with_capacity(capacity),
+            base_offset: 0,
+            buffer: BytesMut::with_capacity(capacity),
+            contigs: Vec::new(),
+            max_holes: None,
         }
     }
 
-    fn write_offset_at_index(
-        &mut self,
-        index: usize,
-        offset: usize,
-        bytes: &[u8],
-    ) -> Result<(), Error> {
-        use std::cmp::Ordering;
-        let segment = &mut self.segments[index];
-        if segment.status == Status::Received {
-            return Err(Error::WouldOverwrite);
+    /// Creates a new `BytesQuilt` with the specified capacity that
+    /// rejects any write which would push the number of tracked
+    /// missing segments above `max_holes`.
+    ///
+    /// This bounds the memory an adversarial or lossy sender can force
+    /// `self.contigs` to grow to by scattering tiny fragments across
+    /// the buffer.
+    pub fn with_capacity_and_max_holes(capacity: usize, max_holes: usize) -> Self {
+        Self {
+            max_holes: Some(max_holes),
+            ..Self::with_capacity(capacity)
+        }
+    }
+
+    fn hole_count(&self) -> usize {
+        self.contigs.iter().filter(|contig| contig.hole_size > 0).count()
+    }
+
+    /// Reports whether writing `len` bytes at `offset` would cause a
+    /// new missing segment to appear, without mutating `self`.
+    ///
+    /// A hole count can only grow when a write lands inside an
+    /// existing hole without touching either edge of it (splitting it
+    /// in two), or when it starts past the end of everything tracked
+    /// so far (opening a brand new hole in between).
+    fn would_add_hole(&self, offset: usize, len: usize) -> bool {
+        let current_end = self.base_offset + self.buffer.len();
+        if offset > current_end {
+            return true;
         }
-        match segment.buffer.capacity().cmp(&bytes.len()) {
-            // TODO[ZS 2023-08-25]: This probably shouldn't even be an error,
-            // we should just grow the buffer.
-            Ordering::Less => return Err(Error::NotEnoughSpace),
-            Ordering::Equal => {
-                segment.status = Status::Received;
-                segment.buffer.put(bytes);
+
+        let mut pos = self.base_offset;
+        for contig in &self.contigs {
+            let hole_start = pos;
+            let data_start = hole_start + contig.hole_size;
+            let data_end = data_start + contig.data_size;
+
+            if offset < data_start {
+                let leading_hole = offset - hole_start;
+                let available = data_start - offset;
+                let trailing_hole = available.saturating_sub(len);
+                return leading_hole > 0 && trailing_hole > 0;
             }
-            Ordering::Greater => {
-                segment.status = Status::Received;
-                segment.buffer.put(bytes);
-                let new_relative_offset = segment.buffer.len();
-                let remaining_segment = segment.buffer.split_off(new_relative_offset);
-                self.segments.insert(
-                    index + 1,
-                    Segment::missing(offset + new_relative_offset, remaining_segment),
-                );
+            if offset < data_end {
+                return false;
             }
-        };
-        Ok(())
+            pos = data_end;
+        }
+        false
+    }
+
+    /// Grows the backing buffer so it covers up to `end`, tracking the
+    /// newly available bytes as a hole (or an extension of the
+    /// trailing hole, if there already is one).
+    fn grow_to(&mut self, end: usize) {
+        let current_end = self.base_offset + self.buffer.len();
+        if end <= current_end {
+            return;
+        }
+
+        let grow_by = end - current_end;
+        self.buffer.resize(self.buffer.len() + grow_by, 0);
+        match self.contigs.last_mut() {
+            Some(last) if last.data_size == 0 => last.hole_size += grow_by,
+            _ => self.contigs.push(Contig {
+                hole_size: grow_by,
+                data_size: 0,
+            }),
+        }
+    }
+
+    /// Merges `self.contigs[index]` into the previous entry when its
+    /// hole has closed completely, joining the two adjacent data runs.
+    fn merge_into_previous(&mut self, index: usize) {
+        if index > 0 && self.contigs[index].hole_size == 0 {
+            let data_size = self.contigs[index].data_size;
+            self.contigs[index - 1].data_size += data_size;
+            self.contigs.remove(index);
+        }
     }
 
     /// Transfer bytes into `self` from `src` at `offset`.
     ///
     /// The `offset` is given from the beginning of the buffer.
     pub fn put_at(&mut self, offset: usize, src: &[u8]) -> Result<Option<MissingSegment>, Error> {
-        let mut missing_segment = None;
-        debug_assert!(
-            self.segments
-                .first()
-                .map(|segment| segment.offset == 0)
-                .unwrap_or(true),
-            "first segment offset should be zero, found {:?}",
-            self.segments.first()
-        );
-        if self.tail_offset > offset {
-            // We should have a missing segment that this offset can write into
-            match self
-                .segments
-                .binary_search_by_key(&offset, |segment| segment.offset)
-            {
-                Ok(index) => {
-                    self.write_offset_at_index(index, offset, src)?;
+        let len = src.len();
+
+        if src.is_empty() {
+            let current_end = self.base_offset + self.buffer.len();
+            let gap = (offset > current_end).then(|| MissingSegment {
+                offset: current_end,
+                length: offset - current_end,
+            });
+            return Ok(gap);
+        }
+
+        if offset < self.base_offset {
+            // This range was already drained by `take_contiguous` or
+            // `Buf::advance`, so it isn't tracked by any contig any more.
+            return Err(Error::WouldOverwrite);
+        }
+
+        if let Some(max_holes) = self.max_holes {
+            if self.hole_count() >= max_holes && self.would_add_hole(offset, len) {
+                return Err(Error::TooManyHoles);
+            }
+        }
+
+        let current_end = self.base_offset + self.buffer.len();
+        let gap = (offset > current_end).then(|| MissingSegment {
+            offset: current_end,
+            length: offset - current_end,
+        });
+
+        // Validate the write against the buffer's current layout before
+        // growing it, so a rejected write never leaves the buffer mutated
+        // (e.g. a spurious trailing hole from a `grow_to` that turned out
+        // not to be needed).
+        if offset < current_end {
+            let mut pos = self.base_offset;
+            for contig in &self.contigs {
+                let hole_start = pos;
+                let data_start = hole_start + contig.hole_size;
+                let data_end = data_start + contig.data_size;
+
+                if offset < data_start {
+                    if len > data_start - offset {
+                        return Err(Error::NotEnoughSpace);
+                    }
+                    break;
                 }
-                Err(index) => {
-                    // This indexing might be safe because the first
-                    // entry in the segments vec should always start
-                    // with `offset = 0`
-                    let segment = &mut self.segments[index - 1];
-                    let to_write_buffer = segment.buffer.split_off(offset - segment.offset);
-                    let segment = Segment::missing(offset, to_write_buffer);
-                    self.segments.insert(index, segment);
-                    self.write_offset_at_index(index, offset, src)?;
+                if offset < data_end {
+                    return Err(Error::WouldOverwrite);
                 }
-            };
-            return Ok(None);
-        } else if self.tail_offset + self.buffer_tail.len() < offset {
-            if !self.buffer_tail.is_empty() {
-                let head_offset = self.tail_offset;
-                let head_received_bytes = self.buffer_tail.split();
-                self.tail_offset += head_received_bytes.len();
-                self.segments
-                    .push(Segment::received(head_offset, head_received_bytes));
+                pos = data_end;
             }
+        }
+
+        self.grow_to(offset + len);
+
+        let mut pos = self.base_offset;
+        for index in 0..self.contigs.len() {
+            let hole_start = pos;
+            let data_start = hole_start + self.contigs[index].hole_size;
+            let data_end = data_start + self.contigs[index].data_size;
+
+            if offset < data_start {
+                let available = data_start - offset;
+
+                let relative = offset - self.base_offset;
+                self.buffer[relative..relative + len].copy_from_slice(src);
+
+                let leading_hole = offset - hole_start;
+                let trailing_hole = available - len;
+                let following_data_size = self.contigs[index].data_size;
+
+                self.contigs[index].hole_size = leading_hole;
+                self.contigs[index].data_size = len;
+                if trailing_hole > 0 {
+                    self.contigs.insert(
+                        index + 1,
+                        Contig {
+                            hole_size: trailing_hole,
+                            data_size: following_data_size,
+                        },
+                    );
+                } else {
+                    self.contigs[index].data_size += following_data_size;
+                }
 
-            let head_offset = self.tail_offset;
-            self.tail_offset = offset;
+                self.merge_into_previous(index);
 
-            let tail_bytes = self.buffer_tail.split_off(offset - head_offset);
-            let head_bytes = mem::replace(&mut self.buffer_tail, tail_bytes);
+                return Ok(gap);
+            }
 
-            // This is true because of the conditional split above to
-            // identify and store a received segment
-            debug_assert!(head_bytes.is_empty());
-            let segment = Segment::missing(head_offset, head_bytes);
-            missing_segment = segment.missing_segment();
-            self.segments.push(segment);
-        } else if self.tail_offset == offset && !self.buffer_tail.is_empty() {
-            // Supposed to write at beginning of tail, but tail is not empty!
-            return Err(Error::WouldOverwrite);
+            if offset < data_end {
+                return Err(Error::WouldOverwrite);
+            }
+
+            pos = data_end;
         }
-        self.buffer_tail.put(src);
-        Ok(missing_segment)
+
+        unreachable!("grow_to guarantees offset falls within the tracked contigs")
     }
 
     /// An iterator over each `MissingSegment` in the `BytesQuilt`.
     pub fn missing_segments(&self) -> impl '_ + Iterator<Item = MissingSegment> {
-        self.segments.iter().filter_map(Segment::missing_segment)
+        let mut pos = self.base_offset;
+        self.contigs.iter().filter_map(move |contig| {
+            let hole_offset = pos;
+            pos += contig.hole_size + contig.data_size;
+            (contig.hole_size > 0).then_some(MissingSegment {
+                offset: hole_offset,
+                length: contig.hole_size,
+            })
+        })
     }
 
-    /// Reassemble the inner `BytesMut` and return it.
-    pub fn into_inner(self) -> BytesMut {
-        let mut segments = self.segments.into_iter();
-        if let Some(segment) = segments.next() {
-            // TODO[ZS 2023-08-25]: initialize these unwritten
-            // sections with zeroes.
-            debug_assert!(
-                !segment.is_missing(),
-                "a segment at offset {} of size {} is missing",
-                segment.offset,
-                segment.buffer.len(),
-            );
-            let mut buffer: BytesMut = segment.buffer;
-            for segment in segments {
-                debug_assert!(
-                    !segment.is_missing(),
-                    "a segment at offset {} of size {} is missing",
-                    segment.offset,
-                    segment.buffer.len(),
-                );
-                buffer.unsplit(segment.buffer);
-            }
-            buffer.unsplit(self.buffer_tail);
-            return buffer;
-        }
-        self.buffer_tail
+    /// Reports whether every byte has been received, i.e. there are no
+    /// missing segments left to fill in.
+    ///
+    /// This lets a caller check completeness before calling
+    /// [`Self::into_inner`] rather than relying on its debug assertion
+    /// to fire.
+    pub fn is_complete(&self) -> bool {
+        self.missing_segments().next().is_none()
     }
-}
 
-impl Segment {
-    fn missing(offset: usize, buffer: BytesMut) -> Self {
-        Self {
-            status: Status::Missing,
-            offset,
-            buffer,
+    /// The number of bytes available at the front of the buffer without
+    /// any intervening missing segments.
+    ///
+    /// This is the length of the `Bytes` that [`Self::take_contiguous`]
+    /// would return, without consuming anything.
+    pub fn contiguous_len(&self) -> usize {
+        match self.contigs.first() {
+            Some(contig) if contig.hole_size == 0 => contig.data_size,
+            _ => 0,
         }
     }
 
-    fn received(offset: usize, buffer: BytesMut) -> Self {
-        Self {
-            status: Status::Received,
-            offset,
-            buffer,
+    /// Removes and returns all contiguously received bytes from the
+    /// front of the buffer, advancing the base offset past them.
+    ///
+    /// This lets a caller drain the reassembled prefix of a
+    /// long-running stream as soon as it becomes available, rather
+    /// than waiting for every hole to be filled before calling
+    /// [`Self::into_inner`].
+    pub fn take_contiguous(&mut self) -> Bytes {
+        let ready = self.contiguous_len();
+        let ready_bytes = self.buffer.split_to(ready);
+        if ready > 0 {
+            self.contigs.remove(0);
         }
+        self.base_offset += ready;
+        ready_bytes.freeze()
+    }
+
+    /// Reassemble the inner `BytesMut` and return it.
+    pub fn into_inner(self) -> BytesMut {
+        // Unwritten sections were zero-filled by `grow_to`, so in
+        // release builds any remaining holes come back as zeroes.
+        debug_assert!(
+            self.is_complete(),
+            "buffer still has missing segments: {:?}",
+            self.missing_segments().collect::<Vec<_>>(),
+        );
+        self.buffer
+    }
+}
+
+impl Buf for BytesQuilt {
+    /// The number of in-order bytes currently available to read.
+    ///
+    /// This mirrors [`Self::contiguous_len`] rather than the total
+    /// size of the buffer, so holes further out are simply not yet
+    /// visible through `Buf`.
+    fn remaining(&self) -> usize {
+        self.contiguous_len()
     }
 
-    fn is_missing(&self) -> bool {
-        self.status == Status::Missing
+    fn chunk(&self) -> &[u8] {
+        &self.buffer[..self.contiguous_len()]
     }
 
-    fn missing_segment(&self) -> Option<MissingSegment> {
-        match self.status {
-            Status::Missing => Some(MissingSegment {
-                offset: self.offset,
-                length: self.buffer.capacity(),
-            }),
-            Status::Received => None,
+    /// Permanently consumes `cnt` bytes from the front of the buffer,
+    /// advancing the base offset past them.
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "cannot advance past the contiguous prefix"
+        );
+        self.buffer.advance(cnt);
+        self.base_offset += cnt;
+        if let Some(first) = self.contigs.first_mut() {
+            first.data_size -= cnt;
+            if first.data_size == 0 {
+                self.contigs.remove(0);
+            }
         }
     }
 }
 
 impl MissingSegment {
+    /// The absolute offset, from the beginning of the buffer, at which
+    /// this missing segment starts.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The number of bytes missing from this segment.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
     /// Returns an iterator of all the absolute offsets for byte
     /// segments of a specific size that can fit within this
     /// `MissingSegment`.
@@ -249,6 +395,7 @@ impl MissingSegment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     mod missing_segment {
         use super::*;
@@ -483,4 +630,186 @@ mod tests {
         buffer.put_at(4, &[2, 1]).expect("write fail");
         assert_eq!(Err(Error::WouldOverwrite), buffer.put_at(4, &[7, 8]));
     }
+
+    #[test]
+    fn fails_to_rewrite_a_segment_already_taken_contiguous() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(0, &[5_u8, 4, 3, 2, 1]).expect("write fail");
+        buffer.take_contiguous();
+
+        assert_eq!(Err(Error::WouldOverwrite), buffer.put_at(2, &[9, 9]));
+    }
+
+    #[test]
+    fn rejected_write_leaves_the_buffer_unchanged() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(5, &[1, 2, 3]).expect("write fail");
+
+        assert_eq!(Err(Error::NotEnoughSpace), buffer.put_at(2, &[0_u8; 10]));
+        assert_eq!(
+            vec![MissingSegment {
+                offset: 0,
+                length: 5
+            }],
+            buffer.missing_segments().collect::<Vec<_>>()
+        );
+
+        buffer.put_at(0, &[9, 9, 9, 9, 9]).expect("write fail");
+        assert!(buffer.is_complete());
+        let bytes = buffer.into_inner();
+        assert_eq!(&[9, 9, 9, 9, 9, 1, 2, 3][..], bytes.as_ref());
+    }
+
+    #[test]
+    fn empty_writes_are_a_no_op() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        assert_eq!(Ok(None), buffer.put_at(0, &[]));
+        assert_eq!(0, buffer.contiguous_len());
+        assert!(buffer.missing_segments().next().is_none());
+
+        assert_eq!(
+            Ok(Some(MissingSegment {
+                offset: 0,
+                length: 5
+            })),
+            buffer.put_at(5, &[])
+        );
+        assert!(buffer.missing_segments().next().is_none());
+
+        buffer.put_at(5, &[5, 4, 3, 2, 1]).expect("write fail");
+        assert_eq!(Ok(None), buffer.put_at(0, &[]));
+        assert_eq!(
+            vec![MissingSegment {
+                offset: 0,
+                length: 5
+            }],
+            buffer.missing_segments().collect::<Vec<_>>()
+        );
+
+        assert_eq!(Ok(None), buffer.put_at(7, &[]));
+        assert_eq!(
+            vec![MissingSegment {
+                offset: 0,
+                length: 5
+            }],
+            buffer.missing_segments().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn buf_remaining_and_chunk_reflect_the_contiguous_prefix() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(0, &[5_u8, 4, 3]).expect("write fail");
+        buffer.put_at(10, &[1, 2]).expect("write fail");
+
+        assert_eq!(3, Buf::remaining(&buffer));
+        assert_eq!(&[5_u8, 4, 3][..], Buf::chunk(&buffer));
+    }
+
+    #[test]
+    fn buf_advance_permanently_consumes_from_the_front() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(0, &[5_u8, 4, 3, 2, 1]).expect("write fail");
+
+        buffer.advance(2);
+        assert_eq!(3, buffer.remaining());
+        assert_eq!(&[3_u8, 2, 1][..], buffer.chunk());
+
+        buffer.put_at(8, &[9]).expect("write fail");
+        buffer.put_at(5, &[6]).expect("write fail");
+        assert_eq!(&[3_u8, 2, 1, 6][..], buffer.chunk());
+    }
+
+    #[test]
+    fn missing_segment_exposes_offset_and_length() {
+        let segment = MissingSegment {
+            offset: 5,
+            length: 10,
+        };
+        assert_eq!(5, segment.offset());
+        assert_eq!(10, segment.length());
+    }
+
+    #[test]
+    fn is_complete_reports_whether_holes_remain() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        assert!(buffer.is_complete());
+
+        buffer.put_at(5, &[5, 4, 3, 2, 1]).expect("write fail");
+        assert!(!buffer.is_complete());
+
+        buffer.put_at(0, &[10, 9, 8, 7, 6]).expect("write fail");
+        assert!(buffer.is_complete());
+    }
+
+    #[test]
+    fn rejects_writes_that_would_exceed_max_holes() {
+        let mut buffer = BytesQuilt::with_capacity_and_max_holes(20, 1);
+        buffer.put_at(5, &[5, 4, 3, 2, 1]).expect("write fail");
+        assert_eq!(
+            Err(Error::TooManyHoles),
+            buffer.put_at(15, &[1, 2, 3, 4, 5])
+        );
+        assert_eq!(
+            vec![MissingSegment {
+                offset: 0,
+                length: 5
+            }],
+            buffer.missing_segments().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn allows_filling_an_existing_hole_at_max_holes() {
+        let mut buffer = BytesQuilt::with_capacity_and_max_holes(20, 1);
+        buffer.put_at(5, &[5, 4, 3, 2, 1]).expect("write fail");
+        buffer.put_at(0, &[10, 9, 8, 7, 6]).expect("write fail");
+        assert!(buffer.missing_segments().next().is_none());
+    }
+
+    #[test]
+    fn take_contiguous_returns_nothing_before_any_writes() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        assert_eq!(0, buffer.contiguous_len());
+        assert_eq!(&[0_u8; 0][..], buffer.take_contiguous().as_ref());
+    }
+
+    #[test]
+    fn take_contiguous_returns_the_in_order_prefix() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(0, &[5_u8, 4, 3]).expect("write fail");
+        buffer.put_at(10, &[1, 2]).expect("write fail");
+
+        assert_eq!(3, buffer.contiguous_len());
+        assert_eq!(&[5_u8, 4, 3][..], buffer.take_contiguous().as_ref());
+        assert_eq!(0, buffer.contiguous_len());
+        assert_eq!(&[0_u8; 0][..], buffer.take_contiguous().as_ref());
+    }
+
+    #[test]
+    fn take_contiguous_can_be_called_repeatedly_as_holes_fill() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(5, &[5, 4, 3, 2, 1]).expect("write fail");
+        assert_eq!(0, buffer.contiguous_len());
+
+        buffer.put_at(0, &[10, 9, 8, 7, 6]).expect("write fail");
+        assert_eq!(
+            &[10, 9, 8, 7, 6, 5, 4, 3, 2, 1][..],
+            buffer.take_contiguous().as_ref()
+        );
+
+        buffer.put_at(10, &[11]).expect("write fail");
+        assert_eq!(&[11][..], buffer.take_contiguous().as_ref());
+    }
+
+    #[test]
+    fn take_contiguous_allows_subsequent_writes_at_absolute_offsets() {
+        let mut buffer = BytesQuilt::with_capacity(20);
+        buffer.put_at(0, &[5_u8, 4, 3]).expect("write fail");
+        buffer.take_contiguous();
+
+        buffer.put_at(8, &[1]).expect("write fail");
+        buffer.put_at(3, &[2]).expect("write fail");
+        assert_eq!(&[2][..], buffer.take_contiguous().as_ref());
+    }
 }